@@ -0,0 +1,510 @@
+// Copyright (c) 2026, Ben Boeckel
+// All rights reserved.
+//
+// Redistribution and use in source and binary forms, with or without modification,
+// are permitted provided that the following conditions are met:
+//
+//     * Redistributions of source code must retain the above copyright notice,
+//       this list of conditions and the following disclaimer.
+//     * Redistributions in binary form must reproduce the above copyright notice,
+//       this list of conditions and the following disclaimer in the documentation
+//       and/or other materials provided with the distribution.
+//     * Neither the name of this project nor the names of its contributors
+//       may be used to endorse or promote products derived from this software
+//       without specific prior written permission.
+//
+// THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS" AND
+// ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE IMPLIED
+// WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+// DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT OWNER OR CONTRIBUTORS BE LIABLE FOR
+// ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL DAMAGES
+// (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR SERVICES;
+// LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER CAUSED AND ON
+// ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT
+// (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
+// SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+//! `procfs` introspection
+//!
+//! The kernel exposes every key and keyring visible to the current process through
+//! `/proc/keys` and per-user quota accounting through `/proc/key-users`. The types here parse
+//! those tables into owned, structured data so that callers may audit or enumerate keys without
+//! scraping the files themselves.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::num::ParseIntError;
+use std::time::Duration;
+use std::vec;
+
+use bitflags::bitflags;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::KeyringSerial;
+
+const KEYS_FILE: &str = "/proc/keys";
+const KEY_USERS_FILE: &str = "/proc/key-users";
+
+bitflags! {
+    /// The state of a key as reported in the `flags` column of `/proc/keys`.
+    pub struct KeyFlags: u32 {
+        /// The key has had its payload instantiated.
+        const INSTANTIATED = 0x01;
+        /// The key has been revoked.
+        const REVOKED = 0x02;
+        /// The key's type has been removed and the key may no longer be used.
+        const DEAD = 0x04;
+        /// The key counts against its owner's quota.
+        const QUOTA = 0x08;
+        /// The key is in the process of being instantiated.
+        const UNDER_CONSTRUCTION = 0x10;
+        /// The key is a negative key (a cached lookup failure).
+        const NEGATIVE = 0x20;
+        /// The key has been invalidated.
+        const INVALID = 0x40;
+    }
+}
+
+/// An error parsing one of the procfs key tables.
+#[derive(Debug)]
+pub enum ParseError {
+    /// A line, or its `flags` column, did not match the expected layout.
+    ///
+    /// Holds the text which failed to match.
+    Malformed(String),
+    /// A numeric field failed to parse.
+    InvalidNumber {
+        /// The name of the field which failed to parse.
+        field: &'static str,
+        /// The underlying parse error.
+        source: ParseIntError,
+    },
+    /// The `serial` field was not a valid key serial number.
+    InvalidSerial(i64),
+    /// The `timeout` field used an unrecognized unit or a unit without a number, or ended in a
+    /// number without a unit.
+    InvalidTimeout(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Malformed(line) => write!(f, "malformed line or field: `{}`", line),
+            ParseError::InvalidNumber {
+                field,
+                source,
+            } => write!(f, "invalid `{}` field: {}", field, source),
+            ParseError::InvalidSerial(serial) => write!(f, "invalid key serial: {}", serial),
+            ParseError::InvalidTimeout(timeout) => {
+                write!(f, "invalid timeout field: `{}`", timeout)
+            },
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::InvalidNumber {
+                source, ..
+            } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+lazy_static! {
+    static ref KEYS_LINE: Regex = Regex::new(
+        r"(?x)
+        ^(?P<serial>[0-9a-f]+)\s+
+        (?P<flags>[A-Za-z-]{7})\s+
+        (?P<usage>\d+)\s+
+        (?P<timeout>\S+)\s+
+        (?P<perm>[0-9a-f]{8})\s+
+        (?P<uid>\d+)\s+
+        (?P<gid>\d+)\s+
+        (?P<type>\S+)\s+
+        (?P<rest>.*)$
+        "
+    )
+    .unwrap();
+}
+
+/// A single row of `/proc/keys`.
+///
+/// This describes one key or keyring visible to the current process.
+#[derive(Debug, Clone)]
+pub struct KeyDescription {
+    /// The key's serial number.
+    pub serial: KeyringSerial,
+    /// The key's current state.
+    pub flags: KeyFlags,
+    /// The number of things referring to this key (other keyrings, the kernel, file descriptors,
+    /// etc.).
+    pub usage: usize,
+    /// When the key will expire.
+    ///
+    /// `None` indicates that the key does not expire.
+    pub timeout: Option<Duration>,
+    /// The raw permissions mask associated with the key.
+    pub permissions: u32,
+    /// The user ID which owns the key.
+    pub uid: libc::uid_t,
+    /// The group ID which owns the key.
+    pub gid: libc::gid_t,
+    /// The name of the key's type.
+    pub type_name: String,
+    /// The key's description.
+    pub description: String,
+    /// A short, type-specific summary of the key's state (for example, the size of its
+    /// payload).
+    pub summary: Option<String>,
+}
+
+fn parse_flags(flags: &str) -> Result<KeyFlags, ParseError> {
+    const ORDER: &[(char, KeyFlags)] = &[
+        ('I', KeyFlags::INSTANTIATED),
+        ('R', KeyFlags::REVOKED),
+        ('D', KeyFlags::DEAD),
+        ('Q', KeyFlags::QUOTA),
+        ('U', KeyFlags::UNDER_CONSTRUCTION),
+        ('N', KeyFlags::NEGATIVE),
+        ('i', KeyFlags::INVALID),
+    ];
+
+    if flags.chars().count() != ORDER.len() {
+        return Err(ParseError::Malformed(flags.into()));
+    }
+
+    let mut result = KeyFlags::empty();
+    for (ch, (letter, flag)) in flags.chars().zip(ORDER) {
+        if ch == *letter {
+            result |= *flag;
+        } else if ch != '-' {
+            return Err(ParseError::Malformed(flags.into()));
+        }
+    }
+    Ok(result)
+}
+
+fn parse_timeout(timeout: &str) -> Result<Option<Duration>, ParseError> {
+    if timeout == "perm" {
+        return Ok(None);
+    }
+    if timeout == "expd" {
+        return Ok(Some(Duration::new(0, 0)));
+    }
+
+    let mut total = Duration::new(0, 0);
+    let mut digits = String::new();
+    for ch in timeout.chars() {
+        if ch.is_ascii_digit() {
+            digits.push(ch);
+            continue;
+        }
+
+        if digits.is_empty() {
+            return Err(ParseError::InvalidTimeout(timeout.into()));
+        }
+        let value: u64 = digits.parse().map_err(|source| {
+            ParseError::InvalidNumber {
+                field: "timeout",
+                source,
+            }
+        })?;
+        digits.clear();
+
+        let component = match ch {
+            's' => Duration::from_secs(value),
+            'm' => Duration::from_secs(value * 60),
+            'h' => Duration::from_secs(value * 60 * 60),
+            'd' => Duration::from_secs(value * 60 * 60 * 24),
+            'w' => Duration::from_secs(value * 60 * 60 * 24 * 7),
+            _ => return Err(ParseError::InvalidTimeout(timeout.into())),
+        };
+        total += component;
+    }
+
+    if !digits.is_empty() {
+        return Err(ParseError::InvalidTimeout(timeout.into()));
+    }
+
+    Ok(Some(total))
+}
+
+fn parse_key(line: &str) -> Result<KeyDescription, ParseError> {
+    let captures = KEYS_LINE
+        .captures(line)
+        .ok_or_else(|| ParseError::Malformed(line.into()))?;
+    let field = |name| captures.name(name).expect("field should be captured").as_str();
+
+    let serial = i64::from_str_radix(field("serial"), 16).map_err(|source| {
+        ParseError::InvalidNumber {
+            field: "serial",
+            source,
+        }
+    })?;
+    let serial = i32::try_from(serial)
+        .ok()
+        .and_then(KeyringSerial::new)
+        .ok_or(ParseError::InvalidSerial(serial))?;
+    let flags = parse_flags(field("flags"))?;
+    let usage = field("usage")
+        .parse()
+        .map_err(|source| ParseError::InvalidNumber {
+            field: "usage",
+            source,
+        })?;
+    let timeout = parse_timeout(field("timeout"))?;
+    let permissions = u32::from_str_radix(field("perm"), 16).map_err(|source| {
+        ParseError::InvalidNumber {
+            field: "perm",
+            source,
+        }
+    })?;
+    let uid = field("uid")
+        .parse()
+        .map_err(|source| ParseError::InvalidNumber {
+            field: "uid",
+            source,
+        })?;
+    let gid = field("gid")
+        .parse()
+        .map_err(|source| ParseError::InvalidNumber {
+            field: "gid",
+            source,
+        })?;
+    let type_name = field("type").into();
+
+    let rest = field("rest");
+    let (description, summary) = match rest.rfind(": ") {
+        Some(pos) => (rest[..pos].into(), Some(rest[pos + 2..].into())),
+        None => (rest.into(), None),
+    };
+
+    Ok(KeyDescription {
+        serial,
+        flags,
+        usage,
+        timeout,
+        permissions,
+        uid,
+        gid,
+        type_name,
+        description,
+        summary,
+    })
+}
+
+/// An iterator over the keys visible to the current process.
+///
+/// Yielded by [`keys`].
+#[derive(Debug)]
+pub struct Keys(vec::IntoIter<Result<KeyDescription, ParseError>>);
+
+impl Iterator for Keys {
+    type Item = Result<KeyDescription, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Keys {}
+
+/// Parse `/proc/keys`, returning an iterator of every key visible to the current process.
+pub fn keys() -> io::Result<Keys> {
+    let data = fs::read_to_string(KEYS_FILE)?;
+    let entries = data
+        .lines()
+        .map(parse_key)
+        .collect::<Vec<_>>()
+        .into_iter();
+    Ok(Keys(entries))
+}
+
+/// Per-user key quota accounting, as reported by `/proc/key-users`.
+#[derive(Debug, Clone, Copy)]
+pub struct KeyUser {
+    /// The number of things referring to this user's key reference.
+    pub usage: usize,
+    /// The number of keys owned by the user.
+    pub nkeys: usize,
+    /// The number of instantiated keys owned by the user.
+    pub nikeys: usize,
+    /// The number of keys counting against the user's quota.
+    pub qnkeys: usize,
+    /// The maximum number of keys the user may own.
+    pub maxkeys: usize,
+    /// The number of bytes counting against the user's quota.
+    pub qnbytes: usize,
+    /// The maximum number of bytes the user's keys may consume.
+    pub maxbytes: usize,
+}
+
+lazy_static! {
+    static ref KEY_USERS_LINE: Regex = Regex::new(
+        " *(?P<uid>\\d+): +\
+         (?P<usage>\\d+) \
+         (?P<nkeys>\\d+)/(?P<nikeys>\\d+) \
+         (?P<qnkeys>\\d+)/(?P<maxkeys>\\d+) \
+         (?P<qnbytes>\\d+)/(?P<maxbytes>\\d+)"
+    )
+    .unwrap();
+}
+
+fn by_name<T>(captures: &regex::Captures, name: &'static str) -> Result<T, ParseError>
+where
+    T: std::str::FromStr<Err = ParseIntError>,
+{
+    let text = captures.name(name).expect("field should be captured").as_str();
+    text.parse().map_err(|source| {
+        ParseError::InvalidNumber {
+            field: name,
+            source,
+        }
+    })
+}
+
+/// Parse `/proc/key-users`, returning each user's key quota accounting keyed by UID.
+///
+/// A row whose counts fail to parse is reported through the map's value as a [`ParseError`]
+/// rather than failing the call. Lines which do not match the table's layout are skipped and
+/// produce no entry, and a UID which fails to parse fails the whole call with
+/// [`io::ErrorKind::InvalidData`].
+pub fn key_users() -> io::Result<HashMap<libc::uid_t, Result<KeyUser, ParseError>>> {
+    let data = fs::read_to_string(KEY_USERS_FILE)?;
+    let mut users = HashMap::new();
+    for captures in (*KEY_USERS_LINE).captures_iter(&data) {
+        let uid = by_name(&captures, "uid")
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        let user = (|| {
+            Ok(KeyUser {
+                usage: by_name(&captures, "usage")?,
+                nkeys: by_name(&captures, "nkeys")?,
+                nikeys: by_name(&captures, "nikeys")?,
+                qnkeys: by_name(&captures, "qnkeys")?,
+                maxkeys: by_name(&captures, "maxkeys")?,
+                qnbytes: by_name(&captures, "qnbytes")?,
+                maxbytes: by_name(&captures, "maxbytes")?,
+            })
+        })();
+        users.insert(uid, user);
+    }
+    Ok(users)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_empty() {
+        assert_eq!(parse_flags("-------").unwrap(), KeyFlags::empty());
+    }
+
+    #[test]
+    fn flags_all_set() {
+        assert_eq!(parse_flags("IRDQUNi").unwrap(), KeyFlags::all());
+    }
+
+    #[test]
+    fn flags_some_set() {
+        assert_eq!(
+            parse_flags("I--Q---").unwrap(),
+            KeyFlags::INSTANTIATED | KeyFlags::QUOTA
+        );
+    }
+
+    #[test]
+    fn flags_wrong_length() {
+        match parse_flags("I--Q--").unwrap_err() {
+            ParseError::Malformed(flags) => assert_eq!(flags, "I--Q--"),
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn flags_wrong_letter() {
+        // `Q` is only valid in the fourth position; here it is given in the second.
+        match parse_flags("IQ-----").unwrap_err() {
+            ParseError::Malformed(flags) => assert_eq!(flags, "IQ-----"),
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn timeout_perm() {
+        assert_eq!(parse_timeout("perm").unwrap(), None);
+    }
+
+    #[test]
+    fn timeout_expired() {
+        assert_eq!(parse_timeout("expd").unwrap(), Some(Duration::new(0, 0)));
+    }
+
+    #[test]
+    fn timeout_single_component() {
+        assert_eq!(parse_timeout("30s").unwrap(), Some(Duration::from_secs(30)));
+        assert_eq!(
+            parse_timeout("5d").unwrap(),
+            Some(Duration::from_secs(5 * 24 * 60 * 60))
+        );
+        assert_eq!(
+            parse_timeout("2w").unwrap(),
+            Some(Duration::from_secs(2 * 7 * 24 * 60 * 60))
+        );
+    }
+
+    #[test]
+    fn timeout_multiple_components() {
+        let expected = Duration::from_secs(3 * 7 * 24 * 60 * 60 + 2 * 24 * 60 * 60 + 60 * 60);
+        assert_eq!(parse_timeout("3w2d1h").unwrap(), Some(expected));
+    }
+
+    #[test]
+    fn timeout_invalid_unit() {
+        match parse_timeout("5x").unwrap_err() {
+            ParseError::InvalidTimeout(timeout) => assert_eq!(timeout, "5x"),
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn key_with_summary() {
+        let line = "3fb247bc I--Q---       1 perm 1f3f0000     0     0 keyring   _uid.0: 1";
+        let key = parse_key(line).unwrap();
+        assert_eq!(key.serial, KeyringSerial::new(0x3fb247bc).unwrap());
+        assert_eq!(key.flags, KeyFlags::INSTANTIATED | KeyFlags::QUOTA);
+        assert_eq!(key.usage, 1);
+        assert_eq!(key.timeout, None);
+        assert_eq!(key.permissions, 0x1f3f0000);
+        assert_eq!(key.uid, 0);
+        assert_eq!(key.gid, 0);
+        assert_eq!(key.type_name, "keyring");
+        assert_eq!(key.description, "_uid.0");
+        assert_eq!(key.summary.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn key_without_summary() {
+        let line = "1a2b3c4d I------      3 5d   3f010000     0     0 user      wibble";
+        let key = parse_key(line).unwrap();
+        assert_eq!(key.description, "wibble");
+        assert_eq!(key.summary, None);
+        assert_eq!(key.timeout, Some(Duration::from_secs(5 * 24 * 60 * 60)));
+    }
+
+    #[test]
+    fn key_malformed_line() {
+        assert!(parse_key("not a valid line").is_err());
+    }
+}
@@ -24,16 +24,14 @@
 // (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE OF THIS
 // SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::collections::HashMap;
 use std::ffi::CStr;
-use std::fs;
 use std::mem;
-use std::str::FromStr;
 
 use lazy_static::lazy_static;
-use regex::{Captures, Regex};
 use semver::{Version, VersionReq};
 
+use crate::proc::{self, KeyUser};
+
 lazy_static! {
     pub static ref KERNEL_VERSION: String = kernel_version();
     pub static ref SEMVER_KERNEL_VERSION: &'static str = semver_kernel_version();
@@ -42,7 +40,7 @@ lazy_static! {
     pub static ref PAGE_SIZE: usize = page_size();
     pub static ref UID: libc::uid_t = getuid();
     pub static ref GID: libc::gid_t = getgid();
-    pub static ref KEY_INFO: KeyQuota = key_user_info();
+    pub static ref KEY_INFO: KeyUser = key_user_info();
 }
 
 // The full version of the running kernel.
@@ -114,80 +112,13 @@ fn page_size() -> usize {
     ret as usize
 }
 
-const KEY_USERS_FILE: &str = "/proc/key-users";
-
-lazy_static! {
-    static ref KEY_USERS: Regex = Regex::new(
-        " *(?P<uid>\\d+): +\
-         (?P<usage>\\d+) \
-         (?P<nkeys>\\d+)/(?P<nikeys>\\d+) \
-         (?P<qnkeys>\\d+)/(?P<maxkeys>\\d+) \
-         (?P<qnbytes>\\d+)/(?P<maxbytes>\\d+)"
-    )
-    .unwrap();
-}
-
-fn by_name<T>(capture: &Captures, name: &str) -> T
-where
-    T: FromStr,
-    T::Err: std::fmt::Display,
-{
-    let cap = capture
-        .name(name)
-        .expect("name should be captured")
-        .as_str();
-    match cap.parse() {
-        Ok(v) => v,
-        Err(err) => panic!("failed to parse {} as an integer: {}", name, err),
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-pub struct KeyQuota {
-    pub usage: usize,
-    pub nkeys: usize,
-    pub nikeys: usize,
-    pub qnkeys: usize,
-    pub maxkeys: usize,
-    pub qnbytes: usize,
-    pub maxbytes: usize,
-}
-
-fn all_key_user_info() -> HashMap<libc::uid_t, KeyQuota> {
-    let data = String::from_utf8(fs::read(KEY_USERS_FILE).unwrap()).unwrap();
-    (*KEY_USERS)
-        .captures_iter(&data)
-        .map(|capture| {
-            let uid = by_name(&capture, "uid");
-            let usage = by_name(&capture, "usage");
-            let nkeys = by_name(&capture, "nkeys");
-            let nikeys = by_name(&capture, "nikeys");
-            let qnkeys = by_name(&capture, "qnkeys");
-            let maxkeys = by_name(&capture, "maxkeys");
-            let qnbytes = by_name(&capture, "qnbytes");
-            let maxbytes = by_name(&capture, "maxbytes");
-
-            (
-                uid,
-                KeyQuota {
-                    usage,
-                    nkeys,
-                    nikeys,
-                    qnkeys,
-                    maxkeys,
-                    qnbytes,
-                    maxbytes,
-                },
-            )
-        })
-        .collect()
-}
-
-fn key_user_info() -> KeyQuota {
+fn key_user_info() -> KeyUser {
     let uid = unsafe { libc::getuid() };
-    *all_key_user_info()
-        .get(&uid)
+    proc::key_users()
+        .expect("failed to read /proc/key-users")
+        .remove(&uid)
         .expect("the current user has no keys?")
+        .expect("failed to parse the current user's key quota")
 }
 
 fn getuid() -> libc::uid_t {